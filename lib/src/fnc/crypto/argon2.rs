@@ -0,0 +1,36 @@
+use crate::err::Error;
+use crate::sql::value::Value;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::rngs::OsRng;
+
+/// The OWASP-recommended argon2id parameters: 19 MiB of memory, two
+/// iterations and a single lane.
+fn hasher() -> Argon2<'static> {
+	let params = Params::new(19456, 2, 1, None).unwrap();
+	Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+/// `crypto::argon2::generate($pass)` — hash a password with a random 16-byte
+/// salt, returning the standard PHC `$argon2id$v=19$...` string.
+pub fn generate((arg,): (Value,)) -> Result<Value, Error> {
+	let pass = arg.as_string();
+	let salt = SaltString::generate(&mut OsRng);
+	let hash = hasher()
+		.hash_password(pass.as_ref(), &salt)
+		.map_err(|e| Error::Internal(e.to_string()))?
+		.to_string();
+	Ok(hash.into())
+}
+
+/// `crypto::argon2::compare($hash, $pass)` — verify a password against a stored
+/// PHC string, recovering the params and salt from the hash and comparing in
+/// constant time.
+pub fn compare((arg, pass): (Value, Value)) -> Result<Value, Error> {
+	let hash = arg.as_string();
+	let pass = pass.as_string();
+	match PasswordHash::new(&hash) {
+		Ok(parsed) => Ok(hasher().verify_password(pass.as_ref(), &parsed).is_ok().into()),
+		Err(_) => Ok(false.into()),
+	}
+}
@@ -0,0 +1,19 @@
+use crate::err::Error;
+use crate::sql::value::Value;
+use bcrypt::{hash, verify, DEFAULT_COST};
+
+/// `crypto::bcrypt::generate($pass)` — hash a password with bcrypt at the
+/// default cost, returning the `$2b$...` string.
+pub fn generate((arg,): (Value,)) -> Result<Value, Error> {
+	let pass = arg.as_string();
+	let hashed = hash(pass, DEFAULT_COST).map_err(|e| Error::Internal(e.to_string()))?;
+	Ok(hashed.into())
+}
+
+/// `crypto::bcrypt::compare($hash, $pass)` — verify a password against a stored
+/// bcrypt hash.
+pub fn compare((arg, pass): (Value, Value)) -> Result<Value, Error> {
+	let hash = arg.as_string();
+	let pass = pass.as_string();
+	Ok(verify(pass, &hash).unwrap_or(false).into())
+}
@@ -0,0 +1,28 @@
+use crate::err::Error;
+use crate::sql::value::Value;
+use pbkdf2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use pbkdf2::Pbkdf2;
+use rand::rngs::OsRng;
+
+/// `crypto::pbkdf2::generate($pass)` — hash a password with PBKDF2 and a random
+/// salt, returning the standard PHC string.
+pub fn generate((arg,): (Value,)) -> Result<Value, Error> {
+	let pass = arg.as_string();
+	let salt = SaltString::generate(&mut OsRng);
+	let hash = Pbkdf2
+		.hash_password(pass.as_ref(), &salt)
+		.map_err(|e| Error::Internal(e.to_string()))?
+		.to_string();
+	Ok(hash.into())
+}
+
+/// `crypto::pbkdf2::compare($hash, $pass)` — verify a password against a stored
+/// PBKDF2 PHC string.
+pub fn compare((arg, pass): (Value, Value)) -> Result<Value, Error> {
+	let hash = arg.as_string();
+	let pass = pass.as_string();
+	match PasswordHash::new(&hash) {
+		Ok(parsed) => Ok(Pbkdf2.verify_password(pass.as_ref(), &parsed).is_ok().into()),
+		Err(_) => Ok(false.into()),
+	}
+}
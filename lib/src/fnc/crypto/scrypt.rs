@@ -0,0 +1,28 @@
+use crate::err::Error;
+use crate::sql::value::Value;
+use rand::rngs::OsRng;
+use scrypt::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use scrypt::Scrypt;
+
+/// `crypto::scrypt::generate($pass)` — hash a password with scrypt and a random
+/// salt, returning the standard PHC string.
+pub fn generate((arg,): (Value,)) -> Result<Value, Error> {
+	let pass = arg.as_string();
+	let salt = SaltString::generate(&mut OsRng);
+	let hash = Scrypt
+		.hash_password(pass.as_ref(), &salt)
+		.map_err(|e| Error::Internal(e.to_string()))?
+		.to_string();
+	Ok(hash.into())
+}
+
+/// `crypto::scrypt::compare($hash, $pass)` — verify a password against a stored
+/// scrypt PHC string.
+pub fn compare((arg, pass): (Value, Value)) -> Result<Value, Error> {
+	let hash = arg.as_string();
+	let pass = pass.as_string();
+	match PasswordHash::new(&hash) {
+		Ok(parsed) => Ok(Scrypt.verify_password(pass.as_ref(), &parsed).is_ok().into()),
+		Err(_) => Ok(false.into()),
+	}
+}
@@ -0,0 +1,45 @@
+use crate::err::Error;
+use crate::sql::value::Value;
+
+pub mod crypto;
+
+/// Dispatch a built-in function by its fully-qualified name.
+///
+/// Argument arity is fixed per function, so a mismatch is reported as
+/// [`Error::InvalidArguments`] rather than silently dropped. New function
+/// families register their names here as they are added.
+pub fn synchronous(name: &str, args: Vec<Value>) -> Result<Value, Error> {
+	match name {
+		"crypto::argon2::compare" => crypto::argon2::compare(two(name, args)?),
+		"crypto::argon2::generate" => crypto::argon2::generate(one(name, args)?),
+		"crypto::bcrypt::compare" => crypto::bcrypt::compare(two(name, args)?),
+		"crypto::bcrypt::generate" => crypto::bcrypt::generate(one(name, args)?),
+		"crypto::pbkdf2::compare" => crypto::pbkdf2::compare(two(name, args)?),
+		"crypto::pbkdf2::generate" => crypto::pbkdf2::generate(one(name, args)?),
+		"crypto::scrypt::compare" => crypto::scrypt::compare(two(name, args)?),
+		"crypto::scrypt::generate" => crypto::scrypt::generate(one(name, args)?),
+		_ => Err(Error::InvalidFunction {
+			name: name.to_owned(),
+		}),
+	}
+}
+
+/// Unpack exactly one argument for a unary function.
+fn one(name: &str, mut args: Vec<Value>) -> Result<(Value,), Error> {
+	match args.len() {
+		1 => Ok((args.remove(0),)),
+		_ => Err(Error::InvalidArguments {
+			name: name.to_owned(),
+		}),
+	}
+}
+
+/// Unpack exactly two arguments for a binary function.
+fn two(name: &str, mut args: Vec<Value>) -> Result<(Value, Value), Error> {
+	match args.len() {
+		2 => Ok((args.remove(0), args.remove(0))),
+		_ => Err(Error::InvalidArguments {
+			name: name.to_owned(),
+		}),
+	}
+}
@@ -0,0 +1,126 @@
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::{Actor, Auth, Level};
+use crate::kvs::Datastore;
+use crate::sql::{Object, Value};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The kind of authentication activity being recorded.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum AuthEvent {
+	Signup,
+	Signin,
+}
+
+/// The outcome of an authentication attempt. Failures carry a typed reason so
+/// operators can query patterns (e.g. repeated `NoRecord` for one scope) that a
+/// single collapsed `InvalidAuth` would hide.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Outcome {
+	Success,
+	Failure(Reason),
+}
+
+/// The specific reason an authentication attempt failed.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Reason {
+	/// Required NS/DB/SC parameters were not supplied.
+	MissingParams,
+	/// The named scope does not exist.
+	ScopeMissing,
+	/// The scope exists but does not permit this operation.
+	NotAllowed,
+	/// The SIGNUP/SIGNIN expression failed to compute.
+	QueryFailed,
+	/// The expression computed but returned no record.
+	NoRecord,
+	/// The record was found but the token could not be signed.
+	TokenEncodeFailed,
+}
+
+/// A structured audit entry stored as a record in the `audit` system table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+	pub at: i64,
+	pub event: AuthEvent,
+	pub outcome: Outcome,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ns: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub db: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sc: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+}
+
+/// Write an audit entry for an authentication attempt, success or failure.
+///
+/// Auditing must never mask the original authentication result, so a failure to
+/// persist the entry is swallowed rather than propagated to the caller.
+#[allow(clippy::too_many_arguments)]
+pub async fn record(
+	kvs: &Datastore,
+	event: AuthEvent,
+	outcome: Outcome,
+	ns: Option<String>,
+	db: Option<String>,
+	sc: Option<String>,
+	id: Option<String>,
+) {
+	let entry = AuditEntry {
+		at: Utc::now().timestamp(),
+		event,
+		outcome,
+		ns,
+		db,
+		sc,
+		id,
+	};
+	let _ = store(kvs, &entry).await;
+}
+
+/// A fully-privileged session used to persist audit entries.
+///
+/// Failed-login attempts arrive on an unauthenticated session, which would be
+/// denied `CREATE audit` — exactly the events most worth recording. Auditing
+/// therefore writes with its own root session rather than the caller's.
+fn system_session() -> Session {
+	let mut session = Session::default();
+	session.au = Arc::new(Auth::new(Actor::new("audit".to_owned(), Default::default(), Level::Kv)));
+	session
+}
+
+/// Persist an audit entry as a record in the `audit` system table.
+///
+/// The entry is written with `CREATE audit CONTENT ...` so it lands as a real
+/// row with a unique generated id — concurrent attempts in the same second no
+/// longer overwrite one another — and operators can `SELECT` failed-login
+/// patterns directly.
+async fn store(kvs: &Datastore, entry: &AuditEntry) -> Result<(), Error> {
+	let mut content: BTreeMap<String, Value> = BTreeMap::new();
+	content.insert("at".to_owned(), entry.at.into());
+	content.insert("event".to_owned(), format!("{:?}", entry.event).into());
+	content.insert("outcome".to_owned(), format!("{:?}", entry.outcome).into());
+	if let Some(ref v) = entry.ns {
+		content.insert("ns".to_owned(), v.to_owned().into());
+	}
+	if let Some(ref v) = entry.db {
+		content.insert("db".to_owned(), v.to_owned().into());
+	}
+	if let Some(ref v) = entry.sc {
+		content.insert("sc".to_owned(), v.to_owned().into());
+	}
+	if let Some(ref v) = entry.id {
+		content.insert("id".to_owned(), v.to_owned().into());
+	}
+	let mut vars: BTreeMap<String, Value> = BTreeMap::new();
+	vars.insert("audit".to_owned(), Value::Object(Object(content)));
+	// Write with a root session so failed-login events are never denied
+	let session = system_session();
+	kvs.execute("CREATE audit CONTENT $audit", &session, Some(vars), false).await?;
+	Ok(())
+}
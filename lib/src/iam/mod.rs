@@ -0,0 +1,9 @@
+//! Identity and access management: scope authentication, token issuing,
+//! refresh/rotation, revocation, verification and auditing.
+
+pub mod audit;
+pub mod refresh;
+pub mod revoke;
+pub mod signup;
+pub mod token;
+pub mod verify;
@@ -0,0 +1,223 @@
+use crate::cnf::SERVER_NAME;
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::signup::signing_key;
+use crate::iam::token::Claims;
+use crate::iam::Auth;
+use crate::iam::{Actor, Level};
+use crate::kvs::Datastore;
+use crate::sql::thing::Thing;
+use crate::sql::Value;
+use chrono::{Duration, Utc};
+use jsonwebtoken::encode;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Datastore key under which a refresh token is persisted, keyed by its id.
+fn key(id: &str) -> Vec<u8> {
+	format!("!rt\x00{}", id).into_bytes()
+}
+
+/// Secondary-index key grouping every refresh token by the record it was issued
+/// to, so an entire chain can be located and torn down on replay detection.
+fn index_key(rt: &RefreshToken) -> Vec<u8> {
+	let mut k = index_prefix(&rt.ns, &rt.db, &rt.sc, &rt.rid.to_raw());
+	k.extend_from_slice(rt.id.as_bytes());
+	k
+}
+
+/// The key prefix shared by every refresh token issued to one record.
+fn index_prefix(ns: &str, db: &str, sc: &str, rid: &str) -> Vec<u8> {
+	format!("!rti\x00{}\x00{}\x00{}\x00{}\x00", ns, db, sc, rid).into_bytes()
+}
+
+/// Number of random bytes making up the secret half of a refresh token.
+const SECRET_LEN: usize = 32;
+
+/// Grace period, in seconds, during which a replay of a just-rotated token is
+/// treated as a benign client retry (rejected, but not escalated). Only a
+/// replay seen after this window indicates a genuinely stolen token and tears
+/// the whole chain down.
+const REPLAY_GRACE_SECS: i64 = 10;
+
+/// A persisted refresh token.
+///
+/// The opaque token handed to the client is `"{id}.{secret}"`; only the `id`
+/// is used to look the record up, while `secret` is compared in full on every
+/// refresh so that a leaked id alone cannot rotate the session. This mirrors
+/// the `OauthRefreshToken`/`OauthAccessToken` split used by production auth
+/// backends, keeping refresh state in the datastore instead of the JWT.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RefreshToken {
+	pub id: String,
+	pub ns: String,
+	pub db: String,
+	pub sc: String,
+	pub rid: Thing,
+	pub secret: String,
+	pub iat: i64,
+	pub exp: i64,
+	pub revoked: bool,
+	/// When the token was rotated, used to distinguish a benign client retry
+	/// from a genuine replay of a stolen token.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub revoked_at: Option<i64>,
+}
+
+/// Mint a fresh opaque refresh token for a scope record and persist it.
+///
+/// Returns the `"{id}.{secret}"` string to be stored by the client.
+pub async fn issue(
+	kvs: &Datastore,
+	ns: &str,
+	db: &str,
+	sc: &str,
+	rid: &Thing,
+) -> Result<String, Error> {
+	let id = random_token(SECRET_LEN);
+	let secret = random_token(SECRET_LEN);
+	let now = Utc::now();
+	let rt = RefreshToken {
+		id: id.clone(),
+		ns: ns.to_owned(),
+		db: db.to_owned(),
+		sc: sc.to_owned(),
+		rid: rid.to_owned(),
+		secret: secret.clone(),
+		iat: now.timestamp(),
+		// Refresh tokens live far longer than the access token they renew
+		exp: (now + Duration::days(30)).timestamp(),
+		revoked: false,
+		revoked_at: None,
+	};
+	store(kvs, &rt).await?;
+	Ok(format!("{}.{}", id, secret))
+}
+
+/// Validate a refresh token, rotate it, and mint a fresh access token.
+///
+/// On success the old refresh token is invalidated and a fresh access token
+/// plus its replacement refresh token are returned for the caller to hand back
+/// to the client. If a token is replayed long after it was rotated — the
+/// hallmark of a stolen token — the whole chain for the record is revoked and
+/// `Error::InvalidAuth` is returned.
+pub async fn refresh(
+	kvs: &Datastore,
+	session: &mut Session,
+	token: &str,
+) -> Result<(String, String), Error> {
+	// Split the opaque token into its lookup id and secret halves
+	let (id, secret) = token.split_once('.').ok_or(Error::InvalidAuth)?;
+	// Fetch the stored refresh token
+	let rt = fetch(kvs, id).await?.ok_or(Error::InvalidAuth)?;
+	// Handle a token that has already been rotated
+	if rt.revoked {
+		// A replay within the grace window is almost certainly a client retry
+		// of an in-flight rotation, so reject it without escalating. A replay
+		// seen later means the token was stolen, so the chain is torn down.
+		let age = Utc::now().timestamp() - rt.revoked_at.unwrap_or(0);
+		if age > REPLAY_GRACE_SECS {
+			revoke_chain(kvs, &rt).await?;
+		}
+		return Err(Error::InvalidAuth);
+	}
+	// Compare the secret in full and reject expired tokens
+	if rt.secret != secret || rt.exp < Utc::now().timestamp() {
+		return Err(Error::InvalidAuth);
+	}
+	// Look up the scope so the access token is signed the same way as signup
+	let mut tx = kvs.transaction(false, false).await?;
+	let sv = tx.get_sc(&rt.ns, &rt.db, &rt.sc).await.map_err(|_| Error::InvalidAuth)?;
+	let (header, key) = signing_key(&sv)?;
+	// Mint a fresh short-lived access token
+	let now = Utc::now();
+	let val = Claims {
+		iss: Some(SERVER_NAME.to_owned()),
+		iat: Some(now.timestamp()),
+		nbf: Some(now.timestamp()),
+		exp: Some(
+			match sv.session {
+				Some(v) => now + Duration::from_std(v.0).unwrap(),
+				_ => now + Duration::hours(1),
+			}
+			.timestamp(),
+		),
+		ns: Some(rt.ns.to_owned()),
+		db: Some(rt.db.to_owned()),
+		sc: Some(rt.sc.to_owned()),
+		id: Some(rt.rid.to_raw()),
+		// Fresh jti so the rotated access token is itself revocable
+		jti: Some(super::revoke::new_jti()),
+		..Claims::default()
+	};
+	let tk = encode(&header, &val, &key).map_err(|_| Error::InvalidAuth)?;
+	// Rotate: invalidate the presented token and issue a replacement
+	revoke(kvs, &rt).await?;
+	let next = issue(kvs, &rt.ns, &rt.db, &rt.sc, &rt.rid).await?;
+	// Re-establish the authenticated session
+	session.tk = Some(val.into());
+	session.ns = Some(rt.ns.to_owned());
+	session.db = Some(rt.db.to_owned());
+	session.sc = Some(rt.sc.to_owned());
+	session.sd = Some(Value::from(rt.rid.to_owned()));
+	session.au = Arc::new(Auth::new(Actor::new(
+		rt.rid.to_string(),
+		Default::default(),
+		Level::Scope(rt.ns, rt.db, rt.sc),
+	)));
+	// Hand the rotated pair back to the caller to return to the client
+	Ok((tk, next))
+}
+
+/// Persist a refresh token in the datastore, maintaining the per-record index.
+async fn store(kvs: &Datastore, rt: &RefreshToken) -> Result<(), Error> {
+	let mut tx = kvs.transaction(true, false).await?;
+	let val = serde_json::to_vec(rt).map_err(|_| Error::InvalidAuth)?;
+	tx.set(key(&rt.id), val).await?;
+	// Record the token under its owning record so the chain can be revoked
+	tx.set(index_key(rt), rt.id.as_bytes().to_vec()).await?;
+	tx.commit().await?;
+	Ok(())
+}
+
+/// Fetch a refresh token by its lookup id.
+async fn fetch(kvs: &Datastore, id: &str) -> Result<Option<RefreshToken>, Error> {
+	let mut tx = kvs.transaction(false, false).await?;
+	match tx.get(key(id)).await? {
+		Some(val) => Ok(serde_json::from_slice(&val).ok()),
+		None => Ok(None),
+	}
+}
+
+/// Mark a refresh token as revoked, keeping the row for replay detection.
+async fn revoke(kvs: &Datastore, rt: &RefreshToken) -> Result<(), Error> {
+	let mut next = rt.clone();
+	next.revoked = true;
+	next.revoked_at = Some(Utc::now().timestamp());
+	store(kvs, &next).await
+}
+
+/// Revoke every refresh token issued to the same record, tearing down a chain
+/// that a stolen, replayed token has exposed as compromised.
+async fn revoke_chain(kvs: &Datastore, rt: &RefreshToken) -> Result<(), Error> {
+	let prefix = index_prefix(&rt.ns, &rt.db, &rt.sc, &rt.rid.to_raw());
+	// The index is a contiguous key range; scan up to the next prefix byte
+	let mut end = prefix.clone();
+	end.push(0xff);
+	let mut tx = kvs.transaction(true, false).await?;
+	// Drop every token in the chain along with its index entry
+	for (idx, id) in tx.scan(prefix..end, u32::MAX).await? {
+		tx.del(key(&String::from_utf8_lossy(&id))).await?;
+		tx.del(idx).await?;
+	}
+	tx.commit().await?;
+	Ok(())
+}
+
+/// Generate a URL-safe random token of `len` bytes, hex encoded.
+fn random_token(len: usize) -> String {
+	let mut bytes = vec![0u8; len];
+	rand::thread_rng().fill(&mut bytes[..]);
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,52 @@
+use crate::err::Error;
+use crate::kvs::Datastore;
+use chrono::Utc;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// A revoked token entry, keyed by its `jti` claim.
+///
+/// Entries are stored under their own datastore key, not as records of a
+/// table, so SurrealQL statements (including `DELETE ... LIMIT`) do not see or
+/// prune them, and nothing in this module removes an entry once written.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Revocation {
+	pub jti: String,
+	/// When the token was revoked, in seconds since the Unix epoch.
+	pub at: i64,
+	/// The original token expiry, in seconds since the Unix epoch.
+	pub exp: i64,
+}
+
+/// Datastore key under which a revocation is stored.
+fn key(jti: &str) -> Vec<u8> {
+	format!("!revoked\x00{}", jti).into_bytes()
+}
+
+/// Generate a random `jti` identifier to embed in a token's claims.
+pub fn new_jti() -> String {
+	let mut bytes = [0u8; 16];
+	rand::thread_rng().fill(&mut bytes[..]);
+	bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Revoke a token by its `jti`, recording the original expiry alongside it.
+pub async fn revoke(kvs: &Datastore, jti: &str, exp: i64) -> Result<(), Error> {
+	let entry = Revocation {
+		jti: jti.to_owned(),
+		at: Utc::now().timestamp(),
+		exp,
+	};
+	let mut tx = kvs.transaction(true, false).await?;
+	let val = serde_json::to_vec(&entry).map_err(|e| Error::Internal(e.to_string()))?;
+	tx.set(key(jti), val).await?;
+	tx.commit().await?;
+	Ok(())
+}
+
+/// Check whether a token has been revoked. Called from the token verification
+/// path so a compromised token can be rejected before its `exp`.
+pub async fn is_revoked(kvs: &Datastore, jti: &str) -> Result<bool, Error> {
+	let mut tx = kvs.transaction(false, false).await?;
+	Ok(tx.get(key(jti)).await?.is_some())
+}
@@ -1,16 +1,90 @@
 use crate::cnf::SERVER_NAME;
 use crate::dbs::Session;
 use crate::err::Error;
-use crate::iam::token::{Claims, HEADER};
+use crate::iam::audit::{self, AuthEvent, Outcome, Reason};
+use crate::iam::token::Claims;
 use crate::iam::Auth;
 use crate::iam::{Actor, Level};
 use crate::kvs::Datastore;
+use crate::sql::scope::Scope;
+use crate::sql::Algorithm;
 use crate::sql::Object;
 use crate::sql::Value;
 use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey};
+use jsonwebtoken::{encode, Algorithm as JwtAlgorithm, DecodingKey, EncodingKey, Header};
 use std::sync::Arc;
 
+/// Build the JWT header and signing key for a scope.
+///
+/// When the scope defines an asymmetric algorithm and a private key, the key is
+/// derived from the stored PEM so downstream resource servers can verify with
+/// the matching public key alone. Scopes without a keypair fall back to HS256
+/// signed with the scope `code`, preserving the original symmetric behaviour.
+pub(crate) fn signing_key(sv: &Scope) -> Result<(Header, EncodingKey), Error> {
+	match (&sv.alg, &sv.signing_key) {
+		(Some(alg), Some(key)) => {
+			let enc = match alg {
+				Algorithm::Rs256
+				| Algorithm::Rs384
+				| Algorithm::Rs512
+				| Algorithm::Ps256
+				| Algorithm::Ps384
+				| Algorithm::Ps512 => {
+					EncodingKey::from_rsa_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				Algorithm::Es256 | Algorithm::Es384 => {
+					EncodingKey::from_ec_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				Algorithm::EdDSA => {
+					EncodingKey::from_ed_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				// A symmetric algorithm paired with a PEM key is a misconfiguration,
+				// and ES512 has no jsonwebtoken counterpart, so both are rejected
+				_ => return Err(Error::InvalidAuth),
+			};
+			Ok((Header::new((*alg).into()), enc))
+		}
+		// No keypair defined: keep signing symmetrically with the scope code
+		_ => Ok((Header::default(), EncodingKey::from_secret(sv.code.as_ref()))),
+	}
+}
+
+/// Build the JWT algorithm and decoding key for a scope, used by the token
+/// verification path.
+///
+/// For asymmetric scopes the key is derived from the stored public PEM so a
+/// downstream resource server can verify with the public key alone, never
+/// holding the secret that mints tokens. Scopes without a keypair verify with
+/// HS256 against the scope `code`, matching [`signing_key`].
+pub(crate) fn decoding_key(sv: &Scope) -> Result<(JwtAlgorithm, DecodingKey), Error> {
+	match (&sv.alg, &sv.verify_key) {
+		(Some(alg), Some(key)) => {
+			let dec = match alg {
+				Algorithm::Rs256
+				| Algorithm::Rs384
+				| Algorithm::Rs512
+				| Algorithm::Ps256
+				| Algorithm::Ps384
+				| Algorithm::Ps512 => {
+					DecodingKey::from_rsa_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				Algorithm::Es256 | Algorithm::Es384 => {
+					DecodingKey::from_ec_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				Algorithm::EdDSA => {
+					DecodingKey::from_ed_pem(key.as_ref()).map_err(|_| Error::InvalidAuth)?
+				}
+				// A symmetric algorithm paired with a PEM key is a misconfiguration,
+				// and ES512 has no jsonwebtoken counterpart, so both are rejected
+				_ => return Err(Error::InvalidAuth),
+			};
+			Ok(((*alg).into(), dec))
+		}
+		// No keypair defined: keep verifying symmetrically with the scope code
+		_ => Ok((JwtAlgorithm::HS256, DecodingKey::from_secret(sv.code.as_ref()))),
+	}
+}
+
 pub async fn signup(
 	kvs: &Datastore,
 	session: &mut Session,
@@ -30,7 +104,19 @@ pub async fn signup(
 			// Attempt to signup to specified scope
 			super::signup::sc(kvs, session, ns, db, sc, vars).await
 		}
-		_ => Err(Error::InvalidAuth),
+		_ => {
+			audit::record(
+				kvs,
+				AuthEvent::Signup,
+				Outcome::Failure(Reason::MissingParams),
+				None,
+				None,
+				None,
+				None,
+			)
+			.await;
+			Err(Error::InvalidAuth)
+		}
 	}
 }
 
@@ -60,8 +146,8 @@ pub async fn sc(
 						Ok(val) => match val.record() {
 							// There is a record returned
 							Some(rid) => {
-								// Create the authentication key
-								let key = EncodingKey::from_secret(sv.code.as_ref());
+								// Create the authentication key and header for this scope
+								let (header, key) = signing_key(&sv)?;
 								// Create the authentication claim
 								let val = Claims {
 									iss: Some(SERVER_NAME.to_owned()),
@@ -80,41 +166,83 @@ pub async fn sc(
 									db: Some(db.to_owned()),
 									sc: Some(sc.to_owned()),
 									id: Some(rid.to_raw()),
+									// Unique token id so this token can be revoked before exp
+									jti: Some(super::revoke::new_jti()),
 									..Claims::default()
 								};
 								// Create the authentication token
-								let enc = encode(&HEADER, &val, &key);
+								let enc = encode(&header, &val, &key);
 								// Set the authentication on the session
 								session.tk = Some(val.into());
 								session.ns = Some(ns.to_owned());
 								session.db = Some(db.to_owned());
 								session.sc = Some(sc.to_owned());
 								session.sd = Some(Value::from(rid.to_owned()));
+								// Issue an opaque refresh token so the session can be
+								// renewed without re-running the signup query
+								session.rt = Some(super::refresh::issue(kvs, &ns, &db, &sc, &rid).await?);
 								session.au = Arc::new(Auth::new(Actor::new(
 									rid.to_string(),
 									Default::default(),
-									Level::Scope(ns, db, sc),
+									Level::Scope(ns.clone(), db.clone(), sc.clone()),
 								)));
 								// Create the authentication token
 								match enc {
 									// The auth token was created successfully
-									Ok(tk) => Ok(Some(tk)),
+									Ok(tk) => {
+										audit::record(
+											kvs, AuthEvent::Signup, Outcome::Success,
+											Some(ns), Some(db), Some(sc), Some(rid.to_raw()),
+										).await;
+										Ok(Some(tk))
+									}
 									// There was an error creating the token
-									_ => Err(Error::InvalidAuth),
+									_ => {
+										audit::record(
+											kvs, AuthEvent::Signup,
+											Outcome::Failure(Reason::TokenEncodeFailed),
+											Some(ns), Some(db), Some(sc), Some(rid.to_raw()),
+										).await;
+										Err(Error::InvalidAuth)
+									}
 								}
 							}
 							// No record was returned
-							_ => Err(Error::InvalidAuth),
+							_ => {
+								audit::record(
+									kvs, AuthEvent::Signup, Outcome::Failure(Reason::NoRecord),
+									Some(ns), Some(db), Some(sc), None,
+								).await;
+								Err(Error::InvalidAuth)
+							}
 						},
 						// The signup query failed
-						Err(_) => Err(Error::InvalidAuth),
+						Err(_) => {
+							audit::record(
+								kvs, AuthEvent::Signup, Outcome::Failure(Reason::QueryFailed),
+								Some(ns), Some(db), Some(sc), None,
+							).await;
+							Err(Error::InvalidAuth)
+						}
 					}
 				}
 				// This scope does not allow signup
-				_ => Err(Error::InvalidAuth),
+				_ => {
+					audit::record(
+						kvs, AuthEvent::Signup, Outcome::Failure(Reason::NotAllowed),
+						Some(ns), Some(db), Some(sc), None,
+					).await;
+					Err(Error::InvalidAuth)
+				}
 			}
 		}
 		// The scope does not exists
-		_ => Err(Error::InvalidAuth),
+		_ => {
+			audit::record(
+				kvs, AuthEvent::Signup, Outcome::Failure(Reason::ScopeMissing),
+				Some(ns), Some(db), Some(sc), None,
+			).await;
+			Err(Error::InvalidAuth)
+		}
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,36 @@
+use crate::sql::Value;
+use serde::{Deserialize, Serialize};
+
+/// The registered and SurrealDB-specific claims carried by a scope token.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Claims {
+	#[serde(rename = "iss", skip_serializing_if = "Option::is_none")]
+	pub iss: Option<String>,
+	#[serde(rename = "iat", skip_serializing_if = "Option::is_none")]
+	pub iat: Option<i64>,
+	#[serde(rename = "nbf", skip_serializing_if = "Option::is_none")]
+	pub nbf: Option<i64>,
+	#[serde(rename = "exp", skip_serializing_if = "Option::is_none")]
+	pub exp: Option<i64>,
+	/// Unique token id, used to revoke an individual token before it expires.
+	#[serde(rename = "jti", skip_serializing_if = "Option::is_none")]
+	pub jti: Option<String>,
+	#[serde(rename = "NS", alias = "ns", skip_serializing_if = "Option::is_none")]
+	pub ns: Option<String>,
+	#[serde(rename = "DB", alias = "db", skip_serializing_if = "Option::is_none")]
+	pub db: Option<String>,
+	#[serde(rename = "SC", alias = "sc", skip_serializing_if = "Option::is_none")]
+	pub sc: Option<String>,
+	#[serde(rename = "ID", alias = "id", skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+}
+
+impl From<Claims> for Value {
+	fn from(v: Claims) -> Self {
+		// Represent the claims as a SurrealQL object for the session token
+		match serde_json::to_value(&v) {
+			Ok(j) => j.into(),
+			Err(_) => Value::None,
+		}
+	}
+}
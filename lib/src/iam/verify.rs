@@ -0,0 +1,71 @@
+use crate::dbs::Session;
+use crate::err::Error;
+use crate::iam::revoke;
+use crate::iam::signup::decoding_key;
+use crate::iam::token::Claims;
+use crate::iam::{Actor, Auth, Level};
+use crate::kvs::Datastore;
+use crate::sql::Value;
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use std::sync::Arc;
+
+/// Verify a scope access token and return its claims.
+///
+/// The token is first decoded without checking the signature to discover the
+/// issuing scope, then re-decoded with the scope's [`decoding_key`] so an
+/// asymmetric scope is verified with its public key alone. Finally the `jti` is
+/// checked against the revocation registry, so a compromised token is rejected
+/// before its `exp`.
+pub async fn token(kvs: &Datastore, token: &str) -> Result<Claims, Error> {
+	// Peek at the claims without trusting the signature to locate the scope
+	let mut peek = Validation::default();
+	peek.insecure_disable_signature_validation();
+	peek.validate_exp = false;
+	let unsafe_claims = decode::<Claims>(token, &DecodingKey::from_secret(&[]), &peek)
+		.map_err(|_| Error::InvalidAuth)?
+		.claims;
+	let (ns, db, sc) = match (&unsafe_claims.ns, &unsafe_claims.db, &unsafe_claims.sc) {
+		(Some(ns), Some(db), Some(sc)) => (ns.to_owned(), db.to_owned(), sc.to_owned()),
+		_ => return Err(Error::InvalidAuth),
+	};
+	// Load the scope and build the matching verification key
+	let mut tx = kvs.transaction(false, false).await?;
+	let sv = tx.get_sc(&ns, &db, &sc).await.map_err(|_| Error::InvalidAuth)?;
+	let (alg, key) = decoding_key(&sv)?;
+	// Verify the signature and standard claims
+	let claims = decode::<Claims>(token, &key, &Validation::new(alg))
+		.map_err(|_| Error::InvalidAuth)?
+		.claims;
+	// Reject the token if its id is in the revocation registry
+	if let Some(jti) = &claims.jti {
+		if revoke::is_revoked(kvs, jti).await? {
+			return Err(Error::InvalidAuth);
+		}
+	}
+	Ok(claims)
+}
+
+/// Authenticate a session from a scope access token.
+///
+/// The token is verified with [`token`] — signature, standard claims and the
+/// revocation registry — and, on success, the scope identity is established on
+/// the session just as signup/signin would.
+pub async fn authenticate(
+	kvs: &Datastore,
+	session: &mut Session,
+	tk: &str,
+) -> Result<(), Error> {
+	let claims = token(kvs, tk).await?;
+	let (ns, db, sc) = match (&claims.ns, &claims.db, &claims.sc) {
+		(Some(ns), Some(db), Some(sc)) => (ns.to_owned(), db.to_owned(), sc.to_owned()),
+		_ => return Err(Error::InvalidAuth),
+	};
+	let id = claims.id.clone().unwrap_or_default();
+	session.tk = Some(claims.into());
+	session.ns = Some(ns.clone());
+	session.db = Some(db.clone());
+	session.sc = Some(sc.clone());
+	session.sd = Some(Value::from(id.clone()));
+	session.au = Arc::new(Auth::new(Actor::new(id, Default::default(), Level::Scope(ns, db, sc))));
+	Ok(())
+}
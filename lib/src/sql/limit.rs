@@ -0,0 +1,22 @@
+use crate::sql::comment::shouldbespace;
+use crate::sql::error::IResult;
+use nom::bytes::complete::tag_no_case;
+use nom::character::complete::u64 as uint;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Limit(pub u64);
+
+impl fmt::Display for Limit {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "LIMIT {}", self.0)
+	}
+}
+
+pub fn limit(i: &str) -> IResult<&str, Limit> {
+	let (i, _) = tag_no_case("LIMIT")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, v) = uint(i)?;
+	Ok((i, Limit(v)))
+}
@@ -0,0 +1,161 @@
+use crate::sql::algorithm::{algorithm, Algorithm};
+use crate::sql::comment::shouldbespace;
+use crate::sql::duration::{duration, Duration};
+use crate::sql::error::IResult;
+use crate::sql::ident::{ident, Ident};
+use crate::sql::strand::strand;
+use crate::sql::value::{value, Value};
+use nom::branch::alt;
+use nom::bytes::complete::tag_no_case;
+use nom::combinator::map;
+use nom::multi::many0;
+use nom::sequence::tuple;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The length of the randomly generated HS256 signing secret (`code`).
+const CODE_LEN: usize = 128;
+
+/// A DEFINE SCOPE definition.
+///
+/// `code` is the symmetric secret used when no asymmetric keypair is set. When
+/// `alg` is an asymmetric algorithm, `signing_key` holds the private PEM used to
+/// mint tokens and `verify_key` the public PEM handed to resource servers so
+/// they can verify without ever holding the signing secret.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Scope {
+	pub name: Ident,
+	pub code: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub session: Option<Duration>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub signup: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub signin: Option<Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub alg: Option<Algorithm>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub signing_key: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub verify_key: Option<String>,
+}
+
+impl Scope {
+	/// Generate a fresh random symmetric signing secret.
+	pub fn random_code() -> String {
+		rand::thread_rng().sample_iter(&Alphanumeric).take(CODE_LEN).map(char::from).collect()
+	}
+}
+
+impl fmt::Display for Scope {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "DEFINE SCOPE {}", self.name)?;
+		if let Some(ref v) = self.session {
+			write!(f, " SESSION {}", v)?
+		}
+		if let Some(ref v) = self.signup {
+			write!(f, " SIGNUP {}", v)?
+		}
+		if let Some(ref v) = self.signin {
+			write!(f, " SIGNIN {}", v)?
+		}
+		if let Some(ref v) = self.alg {
+			write!(f, " ALGORITHM {}", v)?
+		}
+		if let Some(ref v) = self.signing_key {
+			write!(f, " SIGNING KEY \"{}\"", v)?
+		}
+		if let Some(ref v) = self.verify_key {
+			write!(f, " VERIFICATION KEY \"{}\"", v)?
+		}
+		Ok(())
+	}
+}
+
+pub fn scope(i: &str) -> IResult<&str, Scope> {
+	let (i, _) = tag_no_case("DEFINE SCOPE")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	let (i, name) = ident(i)?;
+	let (i, opts) = many0(scope_opt)(i)?;
+	// Fold the parsed clauses into the definition
+	let mut res = Scope {
+		name,
+		code: Scope::random_code(),
+		..Default::default()
+	};
+	for opt in opts {
+		match opt {
+			ScopeOpt::Session(v) => res.session = Some(v),
+			ScopeOpt::Signup(v) => res.signup = Some(v),
+			ScopeOpt::Signin(v) => res.signin = Some(v),
+			ScopeOpt::Algorithm(v) => res.alg = Some(v),
+			ScopeOpt::SigningKey(v) => res.signing_key = Some(v),
+			ScopeOpt::VerifyKey(v) => res.verify_key = Some(v),
+		}
+	}
+	Ok((i, res))
+}
+
+enum ScopeOpt {
+	Session(Duration),
+	Signup(Value),
+	Signin(Value),
+	Algorithm(Algorithm),
+	SigningKey(String),
+	VerifyKey(String),
+}
+
+fn scope_opt(i: &str) -> IResult<&str, ScopeOpt> {
+	alt((
+		scope_session,
+		scope_signup,
+		scope_signin,
+		scope_algorithm,
+		scope_signing_key,
+		scope_verify_key,
+	))(i)
+}
+
+fn scope_session(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SESSION")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(duration, ScopeOpt::Session)(i)
+}
+
+fn scope_signup(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SIGNUP")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(value, ScopeOpt::Signup)(i)
+}
+
+fn scope_signin(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("SIGNIN")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(value, ScopeOpt::Signin)(i)
+}
+
+fn scope_algorithm(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tag_no_case("ALGORITHM")(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(algorithm, ScopeOpt::Algorithm)(i)
+}
+
+fn scope_signing_key(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tuple((tag_no_case("SIGNING"), shouldbespace, tag_no_case("KEY")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(strand, |v| ScopeOpt::SigningKey(v.as_string()))(i)
+}
+
+fn scope_verify_key(i: &str) -> IResult<&str, ScopeOpt> {
+	let (i, _) = shouldbespace(i)?;
+	let (i, _) = tuple((tag_no_case("VERIFICATION"), shouldbespace, tag_no_case("KEY")))(i)?;
+	let (i, _) = shouldbespace(i)?;
+	map(strand, |v| ScopeOpt::VerifyKey(v.as_string()))(i)
+}
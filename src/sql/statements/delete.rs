@@ -6,8 +6,14 @@ use crate::dbs::Runtime;
 use crate::err::Error;
 use crate::sql::comment::shouldbespace;
 use crate::sql::cond::{cond, Cond};
+use crate::sql::data::Data;
 use crate::sql::error::IResult;
+use crate::sql::function::Function;
+use crate::sql::idiom::{idiom, Idiom};
+use crate::sql::limit::{limit, Limit};
+use crate::sql::operator::Operator;
 use crate::sql::output::{output, Output};
+use crate::sql::statements::update::UpdateStatement;
 use crate::sql::timeout::{timeout, Timeout};
 use crate::sql::value::{whats, Value, Values};
 use nom::bytes::complete::tag_no_case;
@@ -23,8 +29,12 @@ pub struct DeleteStatement {
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub cond: Option<Cond>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub soft: Option<Idiom>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub output: Option<Output>,
 	#[serde(skip_serializing_if = "Option::is_none")]
+	pub limit: Option<Limit>,
+	#[serde(skip_serializing_if = "Option::is_none")]
 	pub timeout: Option<Timeout>,
 }
 
@@ -38,10 +48,21 @@ impl DeleteStatement {
 	) -> Result<Value, Error> {
 		// Allowed to run?
 		exe.check(opt, Level::No)?;
+		// When soft-delete is requested the records are not physically removed;
+		// instead the configured timestamp field is stamped via an UPDATE so the
+		// rows survive for audit and recovery. Callers that want to hide the
+		// stamped rows filter on the field themselves (`WHERE <field> IS NONE`).
+		if let Some(field) = &self.soft {
+			return self.soft_delete(ctx, opt, exe, doc, field).await;
+		}
 		// Create a new iterator
 		let mut i = Iterator::new();
 		// Pass in statement config
 		i.cond = self.cond.as_ref();
+		// Cap the number of records removed per invocation so admins can expire
+		// rows in bounded batches. The iterator stops collecting once the limit is
+		// reached, mirroring how SELECT applies its own LIMIT.
+		i.limit = self.limit.as_ref().map(|v| v.0 as usize);
 		// Ensure futures are stored
 		let opt = &opt.futures(false);
 		// Loop over the delete targets
@@ -72,6 +93,28 @@ impl DeleteStatement {
 		// Output the results
 		i.output(ctx, exe)
 	}
+
+	/// Stamp the soft-delete timestamp field on every matched record instead of
+	/// removing it, by delegating to an equivalent UPDATE over the same targets.
+	async fn soft_delete(
+		&self,
+		ctx: &Runtime,
+		opt: &Options<'_>,
+		exe: &Executor<'_>,
+		doc: Option<&Value>,
+		field: &Idiom,
+	) -> Result<Value, Error> {
+		// `UPDATE <what> SET <field> = time::now() [WHERE <cond>]`
+		let now = Value::Function(Box::new(Function::Normal("time::now".to_owned(), vec![])));
+		let stm = UpdateStatement {
+			what: self.what.clone(),
+			data: Some(Data::SetExpression(vec![(field.clone(), Operator::Equal, now)])),
+			cond: self.cond.clone(),
+			output: self.output.clone(),
+			timeout: self.timeout.clone(),
+		};
+		stm.compute(ctx, opt, exe, doc).await
+	}
 }
 
 impl fmt::Display for DeleteStatement {
@@ -80,9 +123,15 @@ impl fmt::Display for DeleteStatement {
 		if let Some(ref v) = self.cond {
 			write!(f, " {}", v)?
 		}
+		if let Some(ref v) = self.soft {
+			write!(f, " SOFT {}", v)?
+		}
 		if let Some(ref v) = self.output {
 			write!(f, " {}", v)?
 		}
+		if let Some(ref v) = self.limit {
+			write!(f, " {}", v)?
+		}
 		if let Some(ref v) = self.timeout {
 			write!(f, " {}", v)?
 		}
@@ -96,19 +145,30 @@ pub fn delete(i: &str) -> IResult<&str, DeleteStatement> {
 	let (i, _) = shouldbespace(i)?;
 	let (i, what) = whats(i)?;
 	let (i, cond) = opt(preceded(shouldbespace, cond))(i)?;
+	let (i, soft) = opt(preceded(shouldbespace, soft))(i)?;
 	let (i, output) = opt(preceded(shouldbespace, output))(i)?;
+	let (i, limit) = opt(preceded(shouldbespace, limit))(i)?;
 	let (i, timeout) = opt(preceded(shouldbespace, timeout))(i)?;
 	Ok((
 		i,
 		DeleteStatement {
 			what,
 			cond,
+			soft,
 			output,
+			limit,
 			timeout,
 		},
 	))
 }
 
+fn soft(i: &str) -> IResult<&str, Idiom> {
+	let (i, _) = tag_no_case("SOFT")(i)?;
+	// An explicit field may follow, otherwise default to `deleted_at`
+	let (i, v) = opt(preceded(shouldbespace, idiom))(i)?;
+	Ok((i, v.unwrap_or_else(|| Idiom::from("deleted_at"))))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -122,4 +182,22 @@ mod tests {
 		let out = res.unwrap().1;
 		assert_eq!("DELETE test", format!("{}", out))
 	}
+
+	#[test]
+	fn delete_statement_soft() {
+		let sql = "DELETE test SOFT deleted_at";
+		let res = delete(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("DELETE test SOFT deleted_at", format!("{}", out))
+	}
+
+	#[test]
+	fn delete_statement_limit() {
+		let sql = "DELETE test LIMIT 1000";
+		let res = delete(sql);
+		assert!(res.is_ok());
+		let out = res.unwrap().1;
+		assert_eq!("DELETE test LIMIT 1000", format!("{}", out))
+	}
 }
\ No newline at end of file